@@ -62,7 +62,7 @@ fn main() -> anyhow::Result<()> {
         "cello_open_c.json",
         "tuner_c5.json",
     ];
-    let mut detector = AutocorrelationDetector;
+    let mut detector = AutocorrelationDetector::default();
     plot(
         &mut detector,
         test_signal("tuner_c5.json")?,
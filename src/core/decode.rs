@@ -0,0 +1,144 @@
+use std::path::Path;
+
+/// Loads an audio file into a mono `f64` signal plus its sample rate,
+/// so detectors can run against arbitrary recordings instead of only the
+/// JSON fixtures under `test_data/`.
+///
+/// WAV is decoded with `hound`; other formats fall back to `symphonia` when
+/// the `symphonia` feature is enabled.
+pub fn load_signal(path: impl AsRef<Path>) -> anyhow::Result<(Vec<f64>, f64)> {
+    let path = path.as_ref();
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("wav") => load_wav(path),
+        #[cfg(feature = "symphonia")]
+        _ => load_with_symphonia(path),
+        #[cfg(not(feature = "symphonia"))]
+        _ => Err(anyhow::anyhow!(
+            "unsupported audio format for {:?}; enable the `symphonia` feature for non-WAV files",
+            path
+        )),
+    }
+}
+
+fn load_wav(path: &Path) -> anyhow::Result<(Vec<f64>, f64)> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+    let channels = spec.channels as usize;
+
+    let samples: Vec<f64> = match spec.sample_format {
+        hound::SampleFormat::Int => {
+            let max_amplitude = (1i64 << (spec.bits_per_sample - 1)) as f64;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f64 / max_amplitude))
+                .collect::<Result<_, _>>()?
+        }
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .map(|s| s.map(|s| s as f64))
+            .collect::<Result<_, _>>()?,
+    };
+
+    Ok((downmix_to_mono(&samples, channels), spec.sample_rate as f64))
+}
+
+#[cfg(feature = "symphonia")]
+fn load_with_symphonia(path: &Path) -> anyhow::Result<(Vec<f64>, f64)> {
+    use symphonia::core::{
+        codecs::DecoderOptions, formats::FormatOptions, io::MediaSourceStream,
+        meta::MetadataOptions, probe::Hint,
+    };
+
+    let file = std::fs::File::open(path)?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe().format(
+        &hint,
+        mss,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+    let mut format = probed.format;
+    let track = format
+        .default_track()
+        .ok_or_else(|| anyhow::anyhow!("no default audio track in {:?}", path))?;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| anyhow::anyhow!("unknown sample rate in {:?}", path))? as f64;
+    let track_id = track.id;
+
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+    let mut samples = Vec::new();
+    while let Ok(packet) = format.next_packet() {
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = decoder.decode(&packet)?;
+        let mut buffer = decoded.make_equivalent::<f32>();
+        decoded.convert(&mut buffer);
+        samples.extend(downmix_planes(&buffer).into_iter());
+    }
+
+    Ok((samples, sample_rate))
+}
+
+/// Averages every channel's plane together, sample-for-sample, into a single
+/// mono stream. Each plane holds one channel's samples for the whole packet,
+/// so this is a spatial average across channels rather than the channel
+/// count `downmix_to_mono` chunks a single interleaved stream by.
+#[cfg(feature = "symphonia")]
+fn downmix_planes(buffer: &symphonia::core::audio::AudioBuffer<f32>) -> Vec<f64> {
+    use symphonia::core::audio::Signal;
+
+    let planes = buffer.planes();
+    let channels = planes.planes().len().max(1);
+    let frames = buffer.frames();
+
+    (0..frames)
+        .map(|frame| {
+            planes
+                .planes()
+                .iter()
+                .map(|plane| plane[frame] as f64)
+                .sum::<f64>()
+                / channels as f64
+        })
+        .collect()
+}
+
+fn downmix_to_mono(samples: &[f64], channels: usize) -> Vec<f64> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f64>() / channels as f64)
+        .collect()
+}
+
+#[cfg(all(test, feature = "symphonia"))]
+mod tests {
+    use super::*;
+    use symphonia::core::audio::{AudioBuffer, Channels, Signal, SignalSpec};
+
+    #[test]
+    fn downmix_planes_averages_across_channels_not_time() {
+        let spec = SignalSpec::new(44100, Channels::FRONT_LEFT | Channels::FRONT_RIGHT);
+        let mut buffer: AudioBuffer<f32> = AudioBuffer::new(4, spec);
+        buffer.render_reserved(Some(4));
+        buffer.chan_mut(0).copy_from_slice(&[1.0, 2.0, 3.0, 4.0]);
+        buffer.chan_mut(1).copy_from_slice(&[3.0, 4.0, 5.0, 6.0]);
+
+        // Averaging spatially (across the two channels, per frame) should
+        // give back the per-frame midpoints, not a temporally-chunked
+        // average of one channel's samples.
+        let mono = downmix_planes(&buffer);
+        assert_eq!(mono, vec![2.0, 3.0, 4.0, 5.0]);
+    }
+}
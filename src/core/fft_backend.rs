@@ -0,0 +1,173 @@
+use rustfft::num_complex::Complex64;
+
+/// Abstracts the forward/inverse transform used by the detectors so the same
+/// detector code can run against `rustfft`'s allocating planner on `std`, or
+/// a fixed-size backend on `no_std` targets. Detectors hold an `Fft`
+/// implementor as a field and reuse it across calls instead of calling
+/// `FftPlanner::new()` (or replanning) every time.
+pub trait Fft {
+    fn forward(&mut self, buffer: &mut [Complex64]);
+    fn inverse(&mut self, buffer: &mut [Complex64]);
+}
+
+#[cfg(feature = "std")]
+pub use rustfft_backend::RustFftBackend;
+#[cfg(feature = "std")]
+pub type DefaultFftBackend = RustFftBackend;
+
+#[cfg(feature = "std")]
+mod rustfft_backend {
+    use super::Fft;
+    use rustfft::{num_complex::Complex64, Fft as _, FftPlanner};
+
+    /// Default backend: a single `FftPlanner` reused across calls. The
+    /// planner itself caches plans by length, so as long as the same
+    /// `RustFftBackend` keeps getting reused for the same buffer length
+    /// (which every detector now does), a plan is only ever built once.
+    /// `scratch` is reused the same way: grown on demand rather than
+    /// allocated fresh per call, so the only thing `FftSpace::workspace()`
+    /// hands back unused per transform is a slice, not a fresh buffer.
+    #[derive(Default)]
+    pub struct RustFftBackend {
+        planner: FftPlanner<f64>,
+        scratch: Vec<Complex64>,
+    }
+
+    impl RustFftBackend {
+        fn scratch(&mut self, len: usize) -> &mut [Complex64] {
+            if self.scratch.len() < len {
+                self.scratch.resize(len, Complex64::default());
+            }
+            &mut self.scratch[..len]
+        }
+    }
+
+    impl Fft for RustFftBackend {
+        fn forward(&mut self, buffer: &mut [Complex64]) {
+            let fft = self.planner.plan_fft_forward(buffer.len());
+            let scratch = self.scratch(fft.get_inplace_scratch_len());
+            fft.process_with_scratch(buffer, scratch);
+        }
+
+        fn inverse(&mut self, buffer: &mut [Complex64]) {
+            let fft = self.planner.plan_fft_inverse(buffer.len());
+            let scratch = self.scratch(fft.get_inplace_scratch_len());
+            fft.process_with_scratch(buffer, scratch);
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub use microfft_backend::MicrofftBackend;
+#[cfg(not(feature = "std"))]
+pub type DefaultFftBackend = MicrofftBackend<2048>;
+
+#[cfg(not(feature = "std"))]
+mod microfft_backend {
+    use super::Fft;
+    use microfft::Complex32;
+    use rustfft::num_complex::Complex64;
+
+    /// `no_std` backend built on `microfft`'s fixed-size, non-allocating
+    /// complex transforms. Unlike `rustfft`, `microfft` exposes one function
+    /// per power-of-two size (`cfft_4`, `cfft_8`, ..., `cfft_4096`) operating
+    /// on `[Complex32; N]` in place, and has no inverse transform at all.
+    /// `Fft` is only implemented below for the sizes `microfft` actually
+    /// supports; picking an unsupported `N` is a compile error rather than a
+    /// runtime one. The inverse is derived from the forward transform via
+    /// `ifft(x) = conj(fft(conj(x))) / N`, since that's the only primitive
+    /// `microfft` gives us.
+    ///
+    /// Every `forward`/`inverse` call requires `buffer.len() == N` exactly —
+    /// `microfft`'s transforms are fixed-size, with no padding or resampling
+    /// fallback. Nothing ties a detector's runtime `FftSpace` length to `N`
+    /// at compile time, so building one with `no_std`'s `::default()`
+    /// (`DefaultFftBackend = MicrofftBackend<2048>`) and then feeding it a
+    /// signal/frame of any other length is a runtime panic, not a compile
+    /// error. Construct detectors with a frame/segment length of exactly
+    /// `N` samples (2048 for the default), or pick `MicrofftBackend::<N>`
+    /// explicitly to make the required size a type-level fact at the call
+    /// site.
+    pub struct MicrofftBackend<const N: usize>;
+
+    impl<const N: usize> Default for MicrofftBackend<N> {
+        fn default() -> Self {
+            Self
+        }
+    }
+
+    macro_rules! impl_microfft_backend {
+        ($($n:literal => $cfft:ident),+ $(,)?) => {
+            $(
+                impl Fft for MicrofftBackend<$n> {
+                    fn forward(&mut self, buffer: &mut [Complex64]) {
+                        assert_eq!(
+                            buffer.len(),
+                            $n,
+                            "MicrofftBackend<{}> requires a buffer of exactly {} samples, got {}",
+                            $n,
+                            $n,
+                            buffer.len(),
+                        );
+                        let mut scratch: [Complex32; $n] = core::array::from_fn(|i| {
+                            Complex32::new(buffer[i].re as f32, buffer[i].im as f32)
+                        });
+                        microfft::complex::$cfft(&mut scratch);
+                        for (dst, src) in buffer.iter_mut().zip(scratch.iter()) {
+                            *dst = Complex64::new(src.re as f64, src.im as f64);
+                        }
+                    }
+
+                    fn inverse(&mut self, buffer: &mut [Complex64]) {
+                        for c in buffer.iter_mut() {
+                            *c = c.conj();
+                        }
+                        self.forward(buffer);
+                        let scale = 1.0 / $n as f64;
+                        for c in buffer.iter_mut() {
+                            *c = c.conj() * scale;
+                        }
+                    }
+                }
+            )+
+        };
+    }
+
+    impl_microfft_backend!(
+        4 => cfft_4,
+        8 => cfft_8,
+        16 => cfft_16,
+        32 => cfft_32,
+        64 => cfft_64,
+        128 => cfft_128,
+        256 => cfft_256,
+        512 => cfft_512,
+        1024 => cfft_1024,
+        2048 => cfft_2048,
+        4096 => cfft_4096,
+    );
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use rustfft::num_complex::Complex64;
+
+    #[test]
+    fn test_rustfft_backend_roundtrip() {
+        let mut backend = RustFftBackend::default();
+        let original: Vec<Complex64> = (0..8).map(|i| Complex64::new(i as f64, 0.)).collect();
+        let mut buffer = original.clone();
+
+        backend.forward(&mut buffer);
+        backend.inverse(&mut buffer);
+        for c in buffer.iter_mut() {
+            *c /= buffer.len() as f64;
+        }
+
+        for (original, roundtripped) in original.iter().zip(buffer.iter()) {
+            assert!((original.re - roundtripped.re).abs() < 1e-9);
+            assert!((original.im - roundtripped.im).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::core::fft_space::FftSpace;
+use crate::frequency::FrequencyDetector;
+
+/// Wraps a `FrequencyDetector` with a fixed-capacity ring buffer so live
+/// audio can be fed in small chunks as it arrives, instead of every call
+/// allocating a fresh `FftSpace`. Samples are pushed with `push_samples`;
+/// once `hop_size` new samples have landed since the last estimate, the
+/// most recent `frame_size` samples are re-analyzed in place. Library-level
+/// only for now: nothing in this tree's `tuner/` crate drives this from a
+/// live input yet, so treat that integration as a separate, not-yet-scoped
+/// unit of work rather than part of what landed here.
+pub struct StreamingDetector<D: FrequencyDetector> {
+    detector: D,
+    sample_rate: f64,
+    ring_buffer: Vec<f64>,
+    frame_size: usize,
+    hop_size: usize,
+    write_pos: usize,
+    filled: usize,
+    /// Samples pushed since the last estimate was produced.
+    since_estimate: usize,
+    fft_space: FftSpace,
+}
+
+impl<D: FrequencyDetector> StreamingDetector<D> {
+    pub fn new(detector: D, sample_rate: f64, frame_size: usize, hop_size: usize) -> Self {
+        Self {
+            detector,
+            sample_rate,
+            ring_buffer: vec![0.; frame_size],
+            frame_size,
+            hop_size,
+            write_pos: 0,
+            filled: 0,
+            since_estimate: 0,
+            fft_space: FftSpace::new(frame_size),
+        }
+    }
+
+    /// Feeds new samples into the ring buffer, returning the latest pitch
+    /// estimate if a hop's worth of samples completed a full frame.
+    pub fn push_samples(&mut self, samples: &[f64]) -> Option<f64> {
+        let mut estimate = None;
+        for &sample in samples {
+            self.ring_buffer[self.write_pos] = sample;
+            self.write_pos = (self.write_pos + 1) % self.frame_size;
+            self.filled = (self.filled + 1).min(self.frame_size);
+            self.since_estimate += 1;
+
+            if self.filled == self.frame_size && self.since_estimate >= self.hop_size {
+                self.since_estimate = 0;
+                // Oldest sample is at `write_pos`; read out the buffer in
+                // chronological order without reallocating it.
+                let (tail, head) = self.ring_buffer.split_at(self.write_pos);
+                let frame = head.iter().chain(tail.iter()).copied();
+                estimate = self.detector.detect_frequency_with_fft_space(
+                    frame,
+                    self.sample_rate,
+                    &mut self.fft_space,
+                );
+            }
+        }
+        estimate
+    }
+
+    /// Samples of latency between a sound occurring and it being reflected
+    /// in an estimate: a full frame must accumulate before analysis runs.
+    pub fn latency_samples(&self) -> usize {
+        self.frame_size
+    }
+
+    /// Estimates per second of audio at `self.sample_rate`, i.e. how often
+    /// `push_samples` can be expected to produce a new estimate.
+    pub fn frame_rate(&self) -> f64 {
+        self.sample_rate / self.hop_size as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reports the oldest sample in the frame it's given, so tests can
+    /// assert on exactly what `push_samples` reconstructed from the ring
+    /// buffer rather than on some opaque detected frequency.
+    struct OldestSampleDetector;
+
+    impl FrequencyDetector for OldestSampleDetector {
+        fn detect_frequency_with_fft_space<I: IntoIterator>(
+            &mut self,
+            signal: I,
+            _sample_rate: f64,
+            _fft_space: &mut FftSpace,
+        ) -> Option<f64>
+        where
+            <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
+        {
+            signal.into_iter().next().map(|s| *s.borrow())
+        }
+    }
+
+    #[test]
+    fn no_estimate_until_a_full_frame_has_filled() {
+        let mut detector = StreamingDetector::new(OldestSampleDetector, 44100., 4, 2);
+        assert_eq!(detector.push_samples(&[1., 2.]), None);
+    }
+
+    #[test]
+    fn reads_the_frame_in_chronological_order_across_a_wraparound() {
+        let mut detector = StreamingDetector::new(OldestSampleDetector, 44100., 4, 2);
+
+        // First full frame: oldest sample is the very first one pushed.
+        assert_eq!(detector.push_samples(&[1., 2., 3., 4.]), Some(1.));
+
+        // A new hop overwrites the two oldest slots; the next frame's
+        // oldest sample should be the first surviving one (3.0), not
+        // whatever raw ring-buffer index happens to be read first.
+        assert_eq!(detector.push_samples(&[5., 6.]), Some(3.));
+    }
+}
@@ -0,0 +1,32 @@
+/// Subtracts the arithmetic mean from every sample, removing DC offset
+/// before a signal is transformed. Left uncorrected, a nonzero mean shows up
+/// as energy in the FFT's DC bin and leaks into neighboring low-frequency
+/// bins, which is especially harmful for `AutocorrelationDetector`'s
+/// `f.re / fft_space.space()[0].re` normalization and for the cello open-C
+/// (~64 Hz) case where that leakage is large relative to the fundamental.
+pub fn remove_mean_offset(signal: &mut [f64]) {
+    if signal.is_empty() {
+        return;
+    }
+    let mean = signal.iter().sum::<f64>() / signal.len() as f64;
+    for sample in signal.iter_mut() {
+        *sample -= mean;
+    }
+}
+
+/// Maps a spectral peak's height, relative to the mean across the searched
+/// range, onto a confidence in `0.0..=1.0`. `HpsDetector` and `PowerCepstrum`
+/// both derive their `EnsembleDetector` confidence from a peak/mean ratio
+/// this way rather than each picking its own saturation point, since
+/// `EnsembleDetector` treats the most confident candidate as the reference
+/// octave: if one detector's scale saturates far short of 1.0 relative to
+/// `MpmDetector`'s already-bounded NSDF clarity, it gets out-voted by MPM
+/// regardless of which one is actually right. `saturate_at` is the
+/// peak/mean ratio that counts as full confidence.
+pub fn confidence_from_peak_to_mean_ratio(peak: f64, mean: f64, saturate_at: f64) -> f64 {
+    if mean > 0. {
+        (peak / mean / saturate_at).min(1.0)
+    } else {
+        0.
+    }
+}
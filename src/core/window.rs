@@ -0,0 +1,110 @@
+use std::f64::consts::PI;
+
+/// A windowing function applied to a signal before it's transformed. Each
+/// variant trades main-lobe width (frequency resolution) against side-lobe
+/// suppression (how much energy leaks into neighboring bins) differently,
+/// so detectors expose this as a construction-time choice rather than
+/// hard-coding Hann.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+    BlackmanHarris,
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Window::Hann
+    }
+}
+
+impl Window {
+    pub fn apply(&self, signal: &mut [f64]) {
+        let n = (signal.len().max(2) - 1) as f64;
+        for (i, sample) in signal.iter_mut().enumerate() {
+            *sample *= Self::coefficient(*self, i as f64, n);
+        }
+    }
+
+    fn coefficient(window: Window, i: f64, n: f64) -> f64 {
+        match window {
+            Window::Rectangular => 1.0,
+            Window::Hann => 0.5 * (1. - (2. * PI * i / n).cos()),
+            Window::Hamming => 0.54 - 0.46 * (2. * PI * i / n).cos(),
+            Window::Blackman => {
+                0.42 - 0.5 * (2. * PI * i / n).cos() + 0.08 * (4. * PI * i / n).cos()
+            }
+            Window::BlackmanHarris => {
+                0.35875 - 0.48829 * (2. * PI * i / n).cos() + 0.14128 * (4. * PI * i / n).cos()
+                    - 0.01168 * (6. * PI * i / n).cos()
+            }
+        }
+    }
+
+    /// The coherent gain, i.e. the window's DC-normalized mean value.
+    /// Amplitude-sensitive detectors divide by this to keep bin magnitudes
+    /// comparable across window choices.
+    pub fn coherent_gain(&self) -> f64 {
+        match self {
+            Window::Rectangular => 1.0,
+            Window::Hann => 0.5,
+            Window::Hamming => 0.54,
+            Window::Blackman => 0.42,
+            Window::BlackmanHarris => 0.35875,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rectangular_is_a_no_op() {
+        let mut signal = vec![1., 2., 3., 4.];
+        Window::Rectangular.apply(&mut signal);
+        assert_eq!(signal, vec![1., 2., 3., 4.]);
+    }
+
+    #[test]
+    fn attenuates_the_edges_relative_to_the_center() {
+        for window in [
+            Window::Hann,
+            Window::Hamming,
+            Window::Blackman,
+            Window::BlackmanHarris,
+        ] {
+            let mut signal = vec![1.; 9];
+            window.apply(&mut signal);
+            assert!(
+                signal[0].abs() < signal[4].abs(),
+                "{:?}: edge sample should be attenuated relative to the center",
+                window
+            );
+        }
+    }
+
+    #[test]
+    fn coherent_gain_matches_mean_of_unit_signal() {
+        for window in [
+            Window::Rectangular,
+            Window::Hann,
+            Window::Hamming,
+            Window::Blackman,
+            Window::BlackmanHarris,
+        ] {
+            let mut signal = vec![1.; 4096];
+            window.apply(&mut signal);
+            let mean = signal.iter().sum::<f64>() / signal.len() as f64;
+            assert!(
+                (mean - window.coherent_gain()).abs() < 1e-3,
+                "{:?}: mean {} should match coherent_gain {}",
+                window,
+                mean,
+                window.coherent_gain()
+            );
+        }
+    }
+}
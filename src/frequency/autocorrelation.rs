@@ -1,14 +1,38 @@
 use crate::{
     core::constants::{MAX_FREQ, MIN_FREQ},
+    core::fft_backend::{DefaultFftBackend, Fft},
     core::{fft_space::FftSpace, utils::interpolated_peak_at},
 };
-use rustfft::FftPlanner;
 
 use super::{FftPoint, FrequencyDetector};
 
-pub struct AutocorrelationDetector;
+pub struct AutocorrelationDetector<F: Fft = DefaultFftBackend> {
+    /// Subtract the signal's mean before transforming it. A nonzero DC
+    /// component is especially harmful here, since `unscaled_spectrum`
+    /// normalizes every lag against the DC bin's real part. On by default.
+    remove_dc_offset: bool,
+    fft: F,
+}
+
+impl<F: Fft + Default> Default for AutocorrelationDetector<F> {
+    fn default() -> Self {
+        Self {
+            remove_dc_offset: true,
+            fft: F::default(),
+        }
+    }
+}
 
-impl AutocorrelationDetector {
+impl<F: Fft + Default> AutocorrelationDetector<F> {
+    pub fn new(remove_dc_offset: bool) -> Self {
+        Self {
+            remove_dc_offset,
+            fft: F::default(),
+        }
+    }
+}
+
+impl<F: Fft> AutocorrelationDetector<F> {
     fn relevant_fft_range(sample_rate: f64) -> (usize, usize) {
         // Frequency = SAMPLE_RATE / quefrency
         // With this in mind we can ignore the extremes of the power cepstrum
@@ -32,24 +56,40 @@ impl AutocorrelationDetector {
         )
     }
 
-    fn process_fft<I: IntoIterator>(signal: I, fft_space: &mut FftSpace)
-    where
+    // Takes `remove_dc_offset`/`fft` as plain arguments rather than `&self`
+    // for the same reason as `MpmDetector::process_fft`: so both the real
+    // entry point and `FrequencyDetectorTest::unscaled_spectrum` can share
+    // one body.
+    fn process_fft<I: IntoIterator>(
+        remove_dc_offset: bool,
+        signal: I,
+        fft_space: &mut FftSpace,
+        fft: &mut F,
+    ) where
         <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
     {
-        let mut planner = FftPlanner::new();
-        let forward_fft = planner.plan_fft_forward(fft_space.len());
+        // Rather than collecting `signal` into a scratch `Vec` just to
+        // subtract its mean, write it straight into the already-owned
+        // `fft_space` and subtract the mean there: `fft_space` is the
+        // reusable buffer this whole call chain was threaded through for in
+        // the first place, so there's no need for another allocation.
         fft_space.init_fft_space(signal);
+        if remove_dc_offset {
+            let mean = fft_space.space().iter().map(|f| f.re).sum::<f64>()
+                / fft_space.space().len() as f64;
+            fft_space.map(|f| f - mean);
+        }
 
-        let (space, scratch) = fft_space.workspace();
-        forward_fft.process_with_scratch(space, scratch);
+        let (space, _) = fft_space.workspace();
+        fft.forward(space);
 
         fft_space.map(|f| f * f.conj());
-        let (space, scratch) = fft_space.workspace();
-        let inverse_fft = planner.plan_fft_inverse(space.len());
-        inverse_fft.process_with_scratch(space, scratch);
+        let (space, _) = fft_space.workspace();
+        fft.inverse(space);
     }
 
     fn detect_unscaled_freq<I: IntoIterator>(
+        &mut self,
         signal: I,
         fft_range: (usize, usize),
         fft_space: &mut FftSpace,
@@ -57,7 +97,7 @@ impl AutocorrelationDetector {
     where
         <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
     {
-        Self::process_fft(signal, fft_space);
+        Self::process_fft(self.remove_dc_offset, signal, fft_space, &mut self.fft);
         let unscaled_spectrum: Vec<f64> = Self::unscaled_spectrum(fft_space, fft_range).collect();
         let fft_point = unscaled_spectrum
             .iter()
@@ -73,7 +113,7 @@ impl AutocorrelationDetector {
     }
 }
 
-impl FrequencyDetector for AutocorrelationDetector {
+impl<F: Fft> FrequencyDetector for AutocorrelationDetector<F> {
     fn detect_frequency_with_fft_space<I: IntoIterator>(
         &mut self,
         signal: I,
@@ -84,7 +124,7 @@ impl FrequencyDetector for AutocorrelationDetector {
         <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
     {
         let (lower_limit, upper_limit) = Self::relevant_fft_range(sample_rate);
-        Self::detect_unscaled_freq(signal, (lower_limit, upper_limit), fft_space)
+        self.detect_unscaled_freq(signal, (lower_limit, upper_limit), fft_space)
             .map(|point| sample_rate / (lower_limit as f64 + point.x))
     }
 }
@@ -92,13 +132,15 @@ impl FrequencyDetector for AutocorrelationDetector {
 #[cfg(feature = "test_utils")]
 mod test_utils {
     use crate::{
-        core::{constants::test_utils::AUTOCORRELATION_ALGORITHM, fft_space::FftSpace},
+        core::{
+            constants::test_utils::AUTOCORRELATION_ALGORITHM, fft_backend::Fft, fft_space::FftSpace,
+        },
         frequency::{FftPoint, FrequencyDetectorTest},
     };
 
     use super::AutocorrelationDetector;
 
-    impl FrequencyDetectorTest for AutocorrelationDetector {
+    impl<F: Fft + Default> FrequencyDetectorTest for AutocorrelationDetector<F> {
         fn unscaled_spectrum<'a, I>(&self, signal: I, fft_range: (usize, usize)) -> Vec<f64>
         where
             <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
@@ -111,7 +153,14 @@ mod test_utils {
                     .1
                     .expect("Signal length is not known"),
             );
-            Self::process_fft(signal_iter, &mut fft_space);
+            // See `MpmDetector`'s `test_utils` impl: this only gets `&self`,
+            // so it spins up a throwaway backend rather than `self.fft`.
+            Self::process_fft(
+                self.remove_dc_offset,
+                signal_iter,
+                &mut fft_space,
+                &mut F::default(),
+            );
             Self::unscaled_spectrum(&fft_space, fft_range).collect()
         }
 
@@ -128,7 +177,7 @@ mod test_utils {
         where
             <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
         {
-            Self::detect_unscaled_freq(signal, fft_range, fft_space)
+            self.detect_unscaled_freq(signal, fft_range, fft_space)
         }
 
         fn name(&self) -> &'static str {
@@ -144,7 +193,7 @@ mod tests {
 
     #[test]
     fn test_autocorrelation() -> anyhow::Result<()> {
-        let mut detector = AutocorrelationDetector;
+        let mut detector = AutocorrelationDetector::default();
 
         test_fundamental_freq(&mut detector, "tuner_c5.json", 529.841)?;
         test_fundamental_freq(&mut detector, "cello_open_a.json", 219.634)?;
@@ -156,7 +205,7 @@ mod tests {
 
     #[test]
     fn test_autocorrelation_sine() -> anyhow::Result<()> {
-        let mut detector = AutocorrelationDetector;
+        let mut detector = AutocorrelationDetector::default();
         test_sine_wave(&mut detector, 440.)?;
         Ok(())
     }
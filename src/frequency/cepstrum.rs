@@ -1,14 +1,43 @@
 use crate::core::{
     constants::{MAX_FREQ, MIN_FREQ},
+    fft_backend::{DefaultFftBackend, Fft},
     fft_space::FftSpace,
     peak_iter::FftPeaks,
+    utils::confidence_from_peak_to_mean_ratio,
+    window::Window,
 };
-use rustfft::{num_complex::Complex, FftPlanner};
+use crate::pitch::hanned_fft::prepare_for_fft;
+use rustfft::num_complex::Complex;
 
 use super::{FftPoint, FrequencyDetector};
 
-pub struct PowerCepstrum;
-impl PowerCepstrum {
+pub struct PowerCepstrum<F: Fft = DefaultFftBackend> {
+    window: Window,
+    /// Subtract the signal's mean before transforming it, so a nonzero DC
+    /// component doesn't leak into the low quefrencies. On by default.
+    remove_dc_offset: bool,
+    fft: F,
+}
+
+impl<F: Fft + Default> Default for PowerCepstrum<F> {
+    fn default() -> Self {
+        Self {
+            window: Window::default(),
+            remove_dc_offset: true,
+            fft: F::default(),
+        }
+    }
+}
+
+impl<F: Fft + Default> PowerCepstrum<F> {
+    pub fn new(window: Window, remove_dc_offset: bool) -> Self {
+        Self {
+            window,
+            remove_dc_offset,
+            fft: F::default(),
+        }
+    }
+
     fn relevant_fft_range(sample_rate: f64) -> (usize, usize) {
         // Frequency = SAMPLE_RATE / quefrency
         // With this in mind we can ignore the extremes of the power cepstrum
@@ -33,23 +62,32 @@ impl PowerCepstrum {
         )
     }
 
-    fn process_fft<I: IntoIterator>(signal: I, fft_space: &mut FftSpace)
-    where
+    // Takes `window`/`remove_dc_offset`/`fft` as plain arguments rather than
+    // `&self` for the same reason as `MpmDetector::process_fft`: so both the
+    // real entry point and `FrequencyDetectorTest::unscaled_spectrum` can
+    // share one body.
+    fn process_fft<I: IntoIterator>(
+        window: Window,
+        remove_dc_offset: bool,
+        signal: I,
+        fft_space: &mut FftSpace,
+        fft: &mut F,
+    ) where
         <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
     {
-        let mut planner = FftPlanner::new();
-        let forward_fft = planner.plan_fft_forward(fft_space.len());
-        fft_space.init_fft_space(signal);
+        let mut windowed: Vec<f64> = signal.into_iter().map(|s| *s.borrow()).collect();
+        prepare_for_fft(&mut windowed, window, remove_dc_offset);
+        fft_space.init_fft_space(windowed);
 
-        let (space, scratch) = fft_space.workspace();
-        forward_fft.process_with_scratch(space, scratch);
+        let (space, _) = fft_space.workspace();
+        fft.forward(space);
         fft_space.map(|f| Complex::new(f.norm_sqr().log(std::f64::consts::E), 0.0));
-        let (space, scratch) = fft_space.workspace();
-        let inverse_fft = planner.plan_fft_inverse(space.len());
-        inverse_fft.process_with_scratch(space, scratch);
+        let (space, _) = fft_space.workspace();
+        fft.inverse(space);
     }
 
     fn detect_unscaled_freq<I: IntoIterator>(
+        &mut self,
         signal: I,
         fft_range: (usize, usize),
         fft_space: &mut FftSpace,
@@ -57,7 +95,13 @@ impl PowerCepstrum {
     where
         <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
     {
-        Self::process_fft(signal, fft_space);
+        Self::process_fft(
+            self.window,
+            self.remove_dc_offset,
+            signal,
+            fft_space,
+            &mut self.fft,
+        );
         Self::spectrum(fft_space, fft_range)
             .into_iter()
             .fft_peaks(60, 10.)
@@ -75,7 +119,27 @@ impl PowerCepstrum {
     }
 }
 
-impl FrequencyDetector for PowerCepstrum {
+impl<F: Fft> PowerCepstrum<F> {
+    /// Like `detect_frequency_with_fft_space`, but also reports a confidence
+    /// in `0.0..=1.0` the `EnsembleDetector` can weigh against other
+    /// detectors: the winning quefrency peak's amplitude relative to the
+    /// mean amplitude across the searched range.
+    pub fn detect_with_confidence(&mut self, signal: &[f64], sample_rate: f64) -> Option<(f64, f64)> {
+        let mut fft_space = FftSpace::new(signal.len());
+        let fft_range = Self::relevant_fft_range(sample_rate);
+        let point = self.detect_unscaled_freq(signal.iter().copied(), fft_range, &mut fft_space)?;
+
+        let spectrum: Vec<f64> = Self::spectrum(&fft_space, fft_range).map(|f| f.1).collect();
+        let mean = spectrum.iter().sum::<f64>() / spectrum.len().max(1) as f64;
+        // See `confidence_from_peak_to_mean_ratio` for why this is a shared
+        // scale with `HpsDetector` instead of its own saturation point.
+        let confidence = confidence_from_peak_to_mean_ratio(point.y, mean, 4.);
+
+        Some((sample_rate / point.x, confidence))
+    }
+}
+
+impl<F: Fft> FrequencyDetector for PowerCepstrum<F> {
     fn detect_frequency_with_fft_space<I: IntoIterator>(
         &mut self,
         signal: I,
@@ -86,20 +150,23 @@ impl FrequencyDetector for PowerCepstrum {
         <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
     {
         let fft_range = Self::relevant_fft_range(sample_rate);
-        Self::detect_unscaled_freq(signal, fft_range, fft_space).map(|point| sample_rate / point.x)
+        self.detect_unscaled_freq(signal, fft_range, fft_space)
+            .map(|point| sample_rate / point.x)
     }
 }
 
 #[cfg(feature = "test_utils")]
 mod test_utils {
     use crate::{
-        core::{constants::test_utils::POWER_CEPSTRUM_ALGORITHM, fft_space::FftSpace},
+        core::{
+            constants::test_utils::POWER_CEPSTRUM_ALGORITHM, fft_backend::Fft, fft_space::FftSpace,
+        },
         frequency::{FftPoint, FrequencyDetectorTest},
     };
 
     use super::PowerCepstrum;
 
-    impl FrequencyDetectorTest for PowerCepstrum {
+    impl<F: Fft + Default> FrequencyDetectorTest for PowerCepstrum<F> {
         fn unscaled_spectrum<'a, I>(&self, signal: I, fft_range: (usize, usize)) -> Vec<f64>
         where
             <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
@@ -112,7 +179,15 @@ mod test_utils {
                     .1
                     .expect("Signal length is not known"),
             );
-            Self::process_fft(signal_iter, &mut fft_space);
+            // See `MpmDetector`'s `test_utils` impl: this only gets `&self`,
+            // so it spins up a throwaway backend rather than `self.fft`.
+            Self::process_fft(
+                self.window,
+                self.remove_dc_offset,
+                signal_iter,
+                &mut fft_space,
+                &mut F::default(),
+            );
             Self::spectrum(&fft_space, fft_range).map(|f| f.1).collect()
         }
 
@@ -125,7 +200,7 @@ mod test_utils {
         where
             <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
         {
-            Self::detect_unscaled_freq(signal, fft_range, fft_space)
+            self.detect_unscaled_freq(signal, fft_range, fft_space)
         }
 
         fn name(&self) -> &'static str {
@@ -141,7 +216,7 @@ mod tests {
 
     #[test]
     fn test_power() -> anyhow::Result<()> {
-        let mut detector = PowerCepstrum;
+        let mut detector = PowerCepstrum::default();
 
         // Power cepstrum fails to detect the C5 note, which should be at around 523Hz
         test_fundamental_freq(&mut detector, "tuner_c5.json", 261.591)?;
@@ -0,0 +1,153 @@
+use crate::pitch::hps::HpsDetector;
+
+use super::{cepstrum::PowerCepstrum, mpm::MpmDetector};
+
+/// Confidence-weighted pitch estimate produced by `EnsembleDetector`,
+/// replacing the plain `Option<f64>` the individual detectors return so
+/// callers can suppress display (or any other downstream action) when no
+/// detector is confident about what it heard, rather than trusting
+/// whichever one happened to return `Some`. Library-level only for now:
+/// nothing in this tree's `tuner/` crate consumes it yet, so treat that
+/// integration as a separate, not-yet-scoped unit of work rather than part
+/// of what landed here.
+pub struct EnsembleEstimate {
+    pub frequency: f64,
+    /// `0.0..=1.0`, the mean of the contributing detectors' confidences.
+    /// It does not measure how closely their frequencies agree.
+    pub confidence: f64,
+}
+
+/// Runs `PowerCepstrum`, `MpmDetector` and `HpsDetector` against the same
+/// signal, resolves octave disagreements by snapping each candidate to the
+/// nearest octave of the most confident one, and returns their
+/// confidence-weighted median frequency.
+pub struct EnsembleDetector {
+    cepstrum: PowerCepstrum,
+    mpm: MpmDetector,
+    hps: HpsDetector,
+}
+
+impl Default for EnsembleDetector {
+    fn default() -> Self {
+        Self {
+            cepstrum: PowerCepstrum::default(),
+            mpm: MpmDetector::default(),
+            hps: HpsDetector::default(),
+        }
+    }
+}
+
+impl EnsembleDetector {
+    // Snap `candidate` to whichever octave of itself lands closest to
+    // `reference`, so a detector that locked onto a harmonic or subharmonic
+    // doesn't throw off the combined median.
+    fn snap_to_nearest_octave(candidate: f64, reference: f64) -> f64 {
+        let octave = (reference / candidate).log2().round();
+        candidate * 2f64.powf(octave)
+    }
+
+    fn confidence_weighted_median(mut candidates: Vec<(f64, f64)>) -> Option<f64> {
+        if candidates.is_empty() {
+            return None;
+        }
+        candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        let total_weight: f64 = candidates.iter().map(|(_, weight)| weight).sum();
+        let mut cumulative = 0.;
+        for (frequency, weight) in &candidates {
+            cumulative += weight;
+            if cumulative >= total_weight / 2. {
+                return Some(*frequency);
+            }
+        }
+        candidates.last().map(|(frequency, _)| *frequency)
+    }
+
+    pub fn detect_pitch(&mut self, signal: &[f64], sample_rate: f64) -> Option<EnsembleEstimate> {
+        let mut candidates: Vec<(f64, f64)> = [
+            self.cepstrum.detect_with_confidence(signal, sample_rate),
+            self.mpm.detect_with_confidence(signal, sample_rate),
+            self.hps.detect_with_confidence(signal, sample_rate),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let (reference, _) = candidates
+            .iter()
+            .copied()
+            .reduce(|accum, candidate| if candidate.1 > accum.1 { candidate } else { accum })?;
+        for candidate in candidates.iter_mut() {
+            candidate.0 = Self::snap_to_nearest_octave(candidate.0, reference);
+        }
+
+        let total_weight: f64 = candidates.iter().map(|(_, weight)| weight).sum();
+        let confidence = total_weight / candidates.len() as f64;
+
+        Self::confidence_weighted_median(candidates).map(|frequency| EnsembleEstimate {
+            frequency,
+            confidence,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_utils::test_signal;
+
+    #[test]
+    fn detect_pitch_finds_c5_against_a_real_fixture() -> anyhow::Result<()> {
+        const TEST_SAMPLE_RATE: f64 = 44000.0;
+        let signal = test_signal("tuner_c5.json")?;
+        let mut detector = EnsembleDetector::default();
+
+        let estimate = detector
+            .detect_pitch(&signal, TEST_SAMPLE_RATE)
+            .ok_or(anyhow::anyhow!("Did not get pitch"))?;
+        assert!(
+            (estimate.frequency - 523.251).abs() < 10.,
+            "Expected freq near 523.251, got {}",
+            estimate.frequency
+        );
+        assert!(
+            estimate.confidence > 0.,
+            "Expected nonzero confidence, got {}",
+            estimate.confidence
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn snaps_a_harmonic_down_to_the_reference_octave() {
+        // A detector that locked onto the first harmonic of a 110Hz
+        // fundamental should snap back down to ~110Hz given that reference.
+        let snapped = EnsembleDetector::snap_to_nearest_octave(220., 110.);
+        assert!((snapped - 110.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn snaps_a_subharmonic_up_to_the_reference_octave() {
+        let snapped = EnsembleDetector::snap_to_nearest_octave(55., 110.);
+        assert!((snapped - 110.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn confidence_weighted_median_favors_the_heavier_candidate() {
+        let median = EnsembleDetector::confidence_weighted_median(vec![
+            (100., 0.1),
+            (200., 0.8),
+            (300., 0.1),
+        ]);
+        assert_eq!(median, Some(200.));
+    }
+
+    #[test]
+    fn confidence_weighted_median_of_no_candidates_is_none() {
+        assert_eq!(EnsembleDetector::confidence_weighted_median(vec![]), None);
+    }
+}
@@ -0,0 +1,291 @@
+use crate::{
+    core::constants::{MAX_FREQ, MIN_FREQ},
+    core::fft_backend::{DefaultFftBackend, Fft},
+    core::{fft_space::FftSpace, utils::interpolated_peak_at},
+};
+
+use super::{FftPoint, FrequencyDetector};
+
+/// McLeod Pitch Method detector, built on the Normalized Square Difference
+/// Function (NSDF). Unlike the raw autocorrelation approach this is
+/// octave-robust: rather than picking the global maximum lag, it picks the
+/// first strong peak, which is almost always the fundamental rather than a
+/// harmonic.
+pub struct MpmDetector<F: Fft = DefaultFftBackend> {
+    /// Fraction of the NSDF's global peak a candidate peak must clear to be
+    /// accepted as the fundamental. ~0.9, per McLeod & Wyvill.
+    clarity_threshold: f64,
+    /// Subtract the signal's mean before transforming it, for the same
+    /// reason `AutocorrelationDetector` does: a nonzero DC component leaks
+    /// into every lag of the autocorrelation the NSDF is built from. On by
+    /// default.
+    remove_dc_offset: bool,
+    fft: F,
+}
+
+impl<F: Fft + Default> Default for MpmDetector<F> {
+    fn default() -> Self {
+        Self {
+            clarity_threshold: 0.9,
+            remove_dc_offset: true,
+            fft: F::default(),
+        }
+    }
+}
+
+impl<F: Fft + Default> MpmDetector<F> {
+    pub fn new(clarity_threshold: f64, remove_dc_offset: bool) -> Self {
+        Self {
+            clarity_threshold,
+            remove_dc_offset,
+            fft: F::default(),
+        }
+    }
+}
+
+impl<F: Fft> MpmDetector<F> {
+    fn relevant_fft_range(sample_rate: f64) -> (usize, usize) {
+        let lower_limit = (sample_rate / MAX_FREQ).round() as usize;
+        let upper_limit = (sample_rate / MIN_FREQ).round() as usize;
+        (lower_limit, upper_limit)
+    }
+
+    // Takes `remove_dc_offset`/`fft` as plain arguments rather than `&self`,
+    // so both the real entry point (which reuses `self.fft`) and
+    // `FrequencyDetectorTest::unscaled_spectrum` (which only gets `&self` and
+    // has to spin up a throwaway backend) can share one body.
+    fn process_fft<I: IntoIterator>(
+        remove_dc_offset: bool,
+        signal: I,
+        fft_space: &mut FftSpace,
+        fft: &mut F,
+    ) where
+        <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
+    {
+        // See `AutocorrelationDetector::process_fft`: write straight into
+        // the already-owned `fft_space` and subtract the mean there instead
+        // of collecting `signal` into a scratch `Vec` just to do it, so this
+        // step adds no allocation of its own. The NSDF and peak search that
+        // follow still allocate their own `Vec`s on every call.
+        fft_space.init_fft_space(signal);
+        if remove_dc_offset {
+            let mean = fft_space.space().iter().map(|f| f.re).sum::<f64>()
+                / fft_space.space().len() as f64;
+            fft_space.map(|f| f - mean);
+        }
+
+        let (space, _) = fft_space.workspace();
+        fft.forward(space);
+
+        fft_space.map(|f| f * f.conj());
+        let (space, _) = fft_space.workspace();
+        fft.inverse(space);
+    }
+
+    // m(tau) = sum_j (x[j]^2 + x[j+tau]^2), maintained incrementally from
+    // m(0) = 2 * sum(x^2) by subtracting the terms that fall out of the
+    // window as tau grows.
+    fn running_energy(signal: &[f64]) -> Vec<f64> {
+        let n = signal.len();
+        let mut m = vec![0.0; n];
+        let mut energy: f64 = 2.0 * signal.iter().map(|x| x * x).sum::<f64>();
+        m[0] = energy;
+        for tau in 1..n {
+            energy -= signal[tau - 1] * signal[tau - 1] + signal[n - tau] * signal[n - tau];
+            m[tau] = energy;
+        }
+        m
+    }
+
+    fn nsdf(remove_dc_offset: bool, signal: &[f64], fft_space: &mut FftSpace, fft: &mut F) -> Vec<f64> {
+        Self::process_fft(remove_dc_offset, signal.iter().copied(), fft_space, fft);
+        let n = signal.len();
+        let m = Self::running_energy(signal);
+        fft_space
+            .space()
+            .iter()
+            .take(n)
+            .zip(m.iter())
+            .map(|(r, m)| {
+                if *m == 0.0 {
+                    0.0
+                } else {
+                    2.0 * r.re / (n as f64 * m)
+                }
+            })
+            .collect()
+    }
+
+    fn positive_zero_crossings(nsdf: &[f64]) -> Vec<usize> {
+        nsdf.windows(2)
+            .enumerate()
+            .filter_map(|(i, pair)| {
+                if pair[0] <= 0. && pair[1] > 0. {
+                    Some(i + 1)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn peaks_between_crossings(nsdf: &[f64], crossings: &[usize]) -> Vec<(usize, f64)> {
+        crossings
+            .windows(2)
+            .filter_map(|window| {
+                let (start, end) = (window[0], window[1]);
+                nsdf[start..end]
+                    .iter()
+                    .enumerate()
+                    .reduce(|accum, point| if point.1 > accum.1 { point } else { accum })
+                    .map(|(i, amplitude)| (start + i, *amplitude))
+            })
+            .collect()
+    }
+
+    fn detect_unscaled_freq(
+        signal: &[f64],
+        fft_range: (usize, usize),
+        fft_space: &mut FftSpace,
+        clarity_threshold: f64,
+        remove_dc_offset: bool,
+        fft: &mut F,
+    ) -> Option<FftPoint> {
+        let nsdf = Self::nsdf(remove_dc_offset, signal, fft_space, fft);
+        let crossings = Self::positive_zero_crossings(&nsdf);
+        let peaks = Self::peaks_between_crossings(&nsdf, &crossings);
+
+        let (lower_limit, upper_limit) = fft_range;
+        let global_max = peaks
+            .iter()
+            .map(|(_, amplitude)| *amplitude)
+            .fold(f64::MIN, f64::max);
+
+        let (tau, _) = peaks
+            .iter()
+            .filter(|(tau, _)| *tau >= lower_limit && *tau < upper_limit)
+            .find(|(_, amplitude)| *amplitude > clarity_threshold * global_max)?;
+
+        interpolated_peak_at(&nsdf, *tau)
+    }
+
+    /// Like `detect_frequency_with_fft_space`, but also reports a confidence
+    /// in `0.0..=1.0` the `EnsembleDetector` can weigh against other
+    /// detectors: the NSDF peak height itself, which is already a clarity
+    /// measure bounded by 1.0.
+    pub fn detect_with_confidence(&mut self, signal: &[f64], sample_rate: f64) -> Option<(f64, f64)> {
+        let mut fft_space = FftSpace::new(signal.len());
+        let fft_range = Self::relevant_fft_range(sample_rate);
+        let point = Self::detect_unscaled_freq(
+            signal,
+            fft_range,
+            &mut fft_space,
+            self.clarity_threshold,
+            self.remove_dc_offset,
+            &mut self.fft,
+        )?;
+        Some((sample_rate / point.x, point.y.clamp(0., 1.)))
+    }
+}
+
+impl<F: Fft> FrequencyDetector for MpmDetector<F> {
+    fn detect_frequency_with_fft_space<I: IntoIterator>(
+        &mut self,
+        signal: I,
+        sample_rate: f64,
+        fft_space: &mut FftSpace,
+    ) -> Option<f64>
+    where
+        <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
+    {
+        let signal: Vec<f64> = signal.into_iter().map(|s| *s.borrow()).collect();
+        let fft_range = Self::relevant_fft_range(sample_rate);
+        Self::detect_unscaled_freq(
+            &signal,
+            fft_range,
+            fft_space,
+            self.clarity_threshold,
+            self.remove_dc_offset,
+            &mut self.fft,
+        )
+        .map(|point| sample_rate / point.x)
+    }
+}
+
+#[cfg(feature = "test_utils")]
+mod test_utils {
+    use crate::{
+        core::{constants::test_utils::MPM_ALGORITHM, fft_backend::Fft, fft_space::FftSpace},
+        frequency::{FftPoint, FrequencyDetectorTest},
+    };
+
+    use super::MpmDetector;
+
+    impl<F: Fft + Default> FrequencyDetectorTest for MpmDetector<F> {
+        fn unscaled_spectrum<'a, I>(&self, signal: I, _fft_range: (usize, usize)) -> Vec<f64>
+        where
+            <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
+            I: IntoIterator + 'a,
+        {
+            let signal: Vec<f64> = signal.into_iter().map(|s| *s.borrow()).collect();
+            let mut fft_space = FftSpace::new(signal.len());
+            // This inspection helper only gets `&self`, so it can't reuse
+            // `self.fft`; a throwaway backend is fine since it's not on the
+            // hot detection path.
+            Self::nsdf(self.remove_dc_offset, &signal, &mut fft_space, &mut F::default())
+        }
+
+        fn relevant_fft_range(&self, _fft_space_len: usize, sample_rate: f64) -> (usize, usize) {
+            Self::relevant_fft_range(sample_rate)
+        }
+
+        fn detect_unscaled_freq_with_space<I: IntoIterator>(
+            &mut self,
+            signal: I,
+            fft_range: (usize, usize),
+            fft_space: &mut FftSpace,
+        ) -> Option<FftPoint>
+        where
+            <I as IntoIterator>::Item: std::borrow::Borrow<f64>,
+        {
+            let signal: Vec<f64> = signal.into_iter().map(|s| *s.borrow()).collect();
+            Self::detect_unscaled_freq(
+                &signal,
+                fft_range,
+                fft_space,
+                self.clarity_threshold,
+                self.remove_dc_offset,
+                &mut self.fft,
+            )
+        }
+
+        fn name(&self) -> &'static str {
+            MPM_ALGORITHM
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_utils::{test_fundamental_freq, test_sine_wave};
+
+    #[test]
+    fn test_mpm() -> anyhow::Result<()> {
+        let mut detector = MpmDetector::default();
+
+        test_fundamental_freq(&mut detector, "tuner_c5.json", 523.251)?;
+        test_fundamental_freq(&mut detector, "cello_open_a.json", 219.634)?;
+        test_fundamental_freq(&mut detector, "cello_open_d.json", 146.717)?;
+        test_fundamental_freq(&mut detector, "cello_open_g.json", 97.985)?;
+        test_fundamental_freq(&mut detector, "cello_open_c.json", 64.535)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_mpm_sine() -> anyhow::Result<()> {
+        let mut detector = MpmDetector::default();
+        test_sine_wave(&mut detector, 440.)?;
+        Ok(())
+    }
+}
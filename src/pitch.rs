@@ -1,6 +1,7 @@
 pub mod cepstrum;
 pub mod core;
 pub mod hanned_fft;
+pub mod hps;
 
 // autocorrelation doesn't work well enough yet.
 // pub mod autocorrelation;
@@ -0,0 +1,36 @@
+use rustfft::num_complex::Complex64;
+
+use crate::core::{fft_backend::Fft, utils::remove_mean_offset, window::Window};
+
+/// Subtracts the DC offset (if requested) and applies `window`, in place.
+/// The other half of the window+FFT setup every detector here needs;
+/// factored out on its own so `PowerCepstrum` can share it too without
+/// going through `windowed_spectrum`'s `FftSpace`-free `Vec<Complex64>`,
+/// which it can't reuse: `PowerCepstrum` forward/inverse-transforms through
+/// a caller-owned `FftSpace` instead, to avoid allocating a fresh spectrum
+/// buffer on every call.
+pub fn prepare_for_fft(signal: &mut [f64], window: Window, remove_dc_offset: bool) {
+    if remove_dc_offset {
+        remove_mean_offset(signal);
+    }
+    window.apply(signal);
+}
+
+/// Windows `signal`, forward-transforms it with `fft`, and returns the
+/// resulting complex spectrum. This is the ~15-line window+FFT setup that
+/// `HpsDetector` and `WelchSpectrum` both need before post-processing the
+/// spectrum their own way (magnitude vs. power, full range vs. half), so it
+/// lives here once instead of being copy-pasted per detector.
+pub fn windowed_spectrum<F: Fft>(
+    signal: &[f64],
+    window: Window,
+    remove_dc_offset: bool,
+    fft: &mut F,
+) -> Vec<Complex64> {
+    let mut windowed = signal.to_vec();
+    prepare_for_fft(&mut windowed, window, remove_dc_offset);
+
+    let mut buffer: Vec<Complex64> = windowed.iter().map(|s| Complex64::new(*s, 0.0)).collect();
+    fft.forward(&mut buffer);
+    buffer
+}
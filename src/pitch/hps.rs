@@ -0,0 +1,209 @@
+use std::ops::Range;
+
+use crate::core::fft_backend::{DefaultFftBackend, Fft};
+use crate::core::utils::{confidence_from_peak_to_mean_ratio, interpolated_peak_at};
+use crate::core::window::Window;
+use crate::core::FftPoint;
+use crate::pitch::hanned_fft::windowed_spectrum;
+
+use super::{PitchDetector, SignalToSpectrum};
+
+/// Harmonic Product Spectrum detector. Downsamples the magnitude spectrum by
+/// successive integer factors and multiplies the results together, which
+/// reinforces the fundamental (present in every downsampled copy) relative to
+/// harmonics that only line up in some of them. This is what catches the C5
+/// case where `PowerCepstrum` collapses to the first subharmonic.
+pub struct HpsDetector<F: Fft = DefaultFftBackend> {
+    /// Number of harmonics to multiply together, R in `HPS[k] = prod_{r=1}^{R} |X[r*k]|`.
+    num_harmonics: usize,
+
+    /// If the bin at half the detected peak's frequency carries at least this
+    /// fraction of the peak's HPS energy, report that lower octave instead.
+    octave_correction_ratio: f64,
+
+    window: Window,
+    /// Subtract the signal's mean before windowing it. On by default, for
+    /// the same reason as the other detectors: a nonzero DC component
+    /// otherwise shows up as spurious energy in the low bins.
+    remove_dc_offset: bool,
+    fft_len: usize,
+    fft: F,
+}
+
+impl<F: Fft + Default> Default for HpsDetector<F> {
+    fn default() -> Self {
+        Self {
+            num_harmonics: 5,
+            octave_correction_ratio: 0.2,
+            window: Window::default(),
+            remove_dc_offset: true,
+            fft_len: 0,
+            fft: F::default(),
+        }
+    }
+}
+
+impl<F: Fft + Default> HpsDetector<F> {
+    pub fn new(
+        num_harmonics: usize,
+        octave_correction_ratio: f64,
+        window: Window,
+        remove_dc_offset: bool,
+    ) -> Self {
+        Self {
+            num_harmonics,
+            octave_correction_ratio,
+            window,
+            remove_dc_offset,
+            fft_len: 0,
+            fft: F::default(),
+        }
+    }
+}
+
+impl<F: Fft> HpsDetector<F> {
+    fn magnitude_spectrum(&mut self, signal: &[f64]) -> Vec<f64> {
+        let coherent_gain = self.window.coherent_gain();
+        let buffer = windowed_spectrum(signal, self.window, self.remove_dc_offset, &mut self.fft);
+
+        buffer
+            .iter()
+            .take(buffer.len() / 2 + 1)
+            .map(|c| c.norm() / (buffer.len() as f64 * coherent_gain))
+            .collect()
+    }
+
+    fn harmonic_product_spectrum(magnitude: &[f64], num_harmonics: usize) -> Vec<f64> {
+        let mut hps = magnitude.to_vec();
+        for r in 2..=num_harmonics {
+            for (k, bin) in hps.iter_mut().enumerate() {
+                match magnitude.get(k * r) {
+                    Some(harmonic_magnitude) => *bin *= harmonic_magnitude,
+                    None => *bin = 0.,
+                }
+            }
+        }
+        hps
+    }
+}
+
+impl<F: Fft> SignalToSpectrum for HpsDetector<F> {
+    fn signal_to_spectrum(
+        &mut self,
+        signal: &[f64],
+        freq_range: Option<(Range<f64>, f64)>,
+    ) -> (usize, Vec<f64>) {
+        self.fft_len = signal.len();
+        let magnitude = self.magnitude_spectrum(signal);
+        let hps = Self::harmonic_product_spectrum(&magnitude, self.num_harmonics);
+
+        let (start_bin, end_bin) = match freq_range {
+            Some((range, sample_rate)) => (
+                self.freq_to_bin(range.start, sample_rate).round() as usize,
+                (self.freq_to_bin(range.end, sample_rate).round() as usize).min(hps.len()),
+            ),
+            None => (0, hps.len()),
+        };
+        (start_bin, hps[start_bin..end_bin].to_vec())
+    }
+
+    fn bin_to_freq(&self, bin: f64, sample_rate: f64) -> f64 {
+        bin * sample_rate / self.fft_len as f64
+    }
+
+    fn freq_to_bin(&self, freq: f64, sample_rate: f64) -> f64 {
+        freq * self.fft_len as f64 / sample_rate
+    }
+
+    fn name(&self) -> &'static str {
+        "hps"
+    }
+}
+
+impl<F: Fft> HpsDetector<F> {
+    // Shared by `detect_pitch` and `detect_with_confidence` so both can work
+    // from one already-computed `spectrum`/`max_bin` instead of each running
+    // its own `signal_to_spectrum` pass.
+    fn freq_from_max_bin(
+        &self,
+        start_bin: usize,
+        spectrum: &[f64],
+        max_bin: (usize, &f64),
+        sample_rate: f64,
+    ) -> Option<f64> {
+        let bin = max_bin.0 + start_bin;
+        let half_bin = bin / 2;
+        if half_bin >= start_bin
+            && spectrum[half_bin - start_bin] >= self.octave_correction_ratio * max_bin.1
+        {
+            let FftPoint { x: bin, .. } = interpolated_peak_at(spectrum, half_bin - start_bin)?;
+            return Some(self.bin_to_freq(bin + start_bin as f64, sample_rate));
+        }
+
+        let FftPoint { x: bin, .. } = interpolated_peak_at(spectrum, max_bin.0)?;
+        Some(self.bin_to_freq(bin + start_bin as f64, sample_rate))
+    }
+
+    /// Like `detect_pitch`, but also reports a confidence in `0.0..=1.0`
+    /// the `EnsembleDetector` can weigh against other detectors: the
+    /// winning bin's HPS energy relative to the mean across the spectrum.
+    pub fn detect_with_confidence(
+        &mut self,
+        signal: &[f64],
+        sample_rate: f64,
+    ) -> Option<(f64, f64)> {
+        let (start_bin, spectrum) = self.signal_to_spectrum(signal, None);
+        let max_bin = spectrum
+            .iter()
+            .enumerate()
+            .reduce(|accum, item| if item.1 > accum.1 { item } else { accum })?;
+
+        let mean = spectrum.iter().sum::<f64>() / spectrum.len().max(1) as f64;
+        // See `confidence_from_peak_to_mean_ratio` for why this is a shared
+        // scale with `PowerCepstrum` instead of its own saturation point.
+        let confidence = confidence_from_peak_to_mean_ratio(*max_bin.1, mean, 4.);
+
+        let freq = self.freq_from_max_bin(start_bin, &spectrum, max_bin, sample_rate)?;
+        Some((freq, confidence))
+    }
+}
+
+impl<F: Fft> PitchDetector for HpsDetector<F> {
+    fn detect_pitch(
+        &mut self,
+        signal: &[f64],
+        sample_rate: f64,
+        freq_range_hint: Option<Range<f64>>,
+    ) -> Option<f64> {
+        let (start_bin, spectrum) =
+            self.signal_to_spectrum(signal, freq_range_hint.map(|r| (r, sample_rate)));
+        let max_bin = spectrum
+            .iter()
+            .enumerate()
+            .reduce(|accum, item| if item.1 > accum.1 { item } else { accum })?;
+
+        self.freq_from_max_bin(start_bin, &spectrum, max_bin, sample_rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_utils::test_signal;
+
+    #[test]
+    fn test_hps_c5() -> anyhow::Result<()> {
+        const TEST_SAMPLE_RATE: f64 = 44000.0;
+        let mut detector = HpsDetector::default();
+        let signal = test_signal("tuner_c5.json")?;
+        let freq = detector
+            .detect_pitch(&signal, TEST_SAMPLE_RATE, None)
+            .ok_or(anyhow::anyhow!("Did not get pitch"))?;
+        assert!(
+            (freq - 523.251).abs() < 10.,
+            "Expected freq near 523.251, got {}",
+            freq
+        );
+        Ok(())
+    }
+}